@@ -1,209 +1,406 @@
+mod csrf;
+mod error;
+mod schema;
+
 use actix_cors::Cors;
-use actix_web::{http::header, web, App, HttpServer, Responder, HttpResponse};
+use actix_web::{
+    dev::Payload, http::header, web, App, Error as ActixError, FromRequest, HttpRequest,
+    HttpResponse, HttpServer,
+};
+use argon2::{self, Config as Argon2Config};
+use diesel::prelude::*;
+use diesel::r2d2::{self, ConnectionManager};
+use diesel::sqlite::SqliteConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use error::{map_db_error, ApiError, ApiResponse};
+use futures_util::future::{ready, Ready};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand_core::{OsRng, RngCore};
+use schema::{tasks, users};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use std::collections::HashMap;
-use std::fs;
-use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+type DbPool = r2d2::Pool<ConnectionManager<SqliteConnection>>;
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+// Secret used to sign/verify JWTs. In a real deployment this must come from a
+// secrets manager; for now we fall back to a dev-only default.
+fn jwt_secret() -> String {
+    std::env::var("JWT_SECRET").unwrap_or_else(|_| "dev-secret-change-me".to_string())
+}
+
+fn jwt_expiry_hours() -> u64 {
+    std::env::var("JWT_EXPIRY_HOURS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(24)
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+struct Claims {
+    sub: i64,
+    username: String,
+    exp: usize,
+}
+
+fn hash_password(password: &str) -> Result<String, argon2::Error> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    // Set the variant explicitly so the stored hash is always Argon2id.
+    let config = Argon2Config {
+        variant: argon2::Variant::Argon2id,
+        ..Argon2Config::default()
+    };
+    argon2::hash_encoded(password.as_bytes(), &salt, &config)
+}
+
+fn verify_password(hash: &str, password: &str) -> bool {
+    argon2::verify_encoded(hash, password.as_bytes()).unwrap_or(false)
+}
+
+fn issue_token(user: &User) -> Result<String, jsonwebtoken::errors::Error> {
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is before the epoch")
+        .as_secs()
+        + jwt_expiry_hours() * 3600;
+    let claims = Claims {
+        sub: user.id,
+        username: user.username.clone(),
+        exp: exp as usize,
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret().as_bytes()),
+    )
+}
+
+// Extractor that gates a route behind a valid `Authorization: Bearer <jwt>` header.
+struct AuthenticatedUser {
+    #[allow(dead_code)]
+    claims: Claims,
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = ActixError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        let token = match token {
+            Some(token) => token,
+            None => return ready(Err(actix_web::error::ErrorUnauthorized("Missing bearer token"))),
+        };
+
+        match decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(jwt_secret().as_bytes()),
+            &Validation::default(),
+        ) {
+            Ok(data) => ready(Ok(AuthenticatedUser { claims: data.claims })),
+            Err(_) => ready(Err(actix_web::error::ErrorUnauthorized("Invalid or expired token"))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Insertable, AsChangeset, ToSchema)]
+#[diesel(table_name = tasks)]
 struct Task {
-    id: u64,
+    id: i64,
     name: String,
     completed: bool
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Insertable, AsChangeset, ToSchema)]
+#[diesel(table_name = users)]
 struct User {
-    id: u64,
+    id: i64,
     username: String,
     password: String
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct Database {
-    tasks: HashMap<u64, Task>,
-    users: HashMap<u64, User>
+// CRUD data
+fn insert_task_row(conn: &mut SqliteConnection, task: &Task) -> QueryResult<usize> {
+    diesel::insert_into(tasks::table).values(task).execute(conn)
 }
 
-impl Database {
-    fn new() -> Database { // like a constructor
-        Database {
-            tasks: HashMap::new(),
-            users: HashMap::new()
-        }
-    }
-
-    // CRUD data
-    fn insert(&mut self,  task: Task) {
-        self.tasks.insert(task.id, task);
-    }
+fn get_task_row(conn: &mut SqliteConnection, id: i64) -> QueryResult<Task> {
+    tasks::table.find(id).first(conn)
+}
 
-    fn get(&self, id: u64) -> Option<&Task> {
-        self.tasks.get(&id)
-    }
+fn get_all_task_rows(conn: &mut SqliteConnection) -> QueryResult<Vec<Task>> {
+    tasks::table.load(conn)
+}
 
-    fn get_all(&self) -> Vec<&Task> {
-        self.tasks.values().collect()
-    }
+fn delete_task_row(conn: &mut SqliteConnection, id: i64) -> QueryResult<usize> {
+    diesel::delete(tasks::table.find(id)).execute(conn)
+}
 
-    fn delete(&mut self, id: &u64) {
-        self.tasks.remove(&id);
-    }
+fn update_task_row(conn: &mut SqliteConnection, task: &Task) -> QueryResult<usize> {
+    diesel::update(tasks::table.find(task.id)).set(task).execute(conn)
+}
 
-    fn update(&mut self, id: u64, task: Task) {
-        self.tasks.insert(id, task);
-    }
+// USER DATA RELATED FUNCTIONS
+fn insert_user(conn: &mut SqliteConnection, user: &User) -> QueryResult<usize> {
+    diesel::insert_into(users::table).values(user).execute(conn)
+}
 
-    // USER DATA RELATED FUNCTIONS
-    fn insert_user(&mut self, user: User) {
-        self.users.insert(user.id, user);
-    }
+fn get_user_by_name(conn: &mut SqliteConnection, username: &str) -> QueryResult<User> {
+    users::table.filter(users::username.eq(username)).first(conn)
+}
 
-    fn get_user_by_name(&self, username: &str) -> Option<&User> {
-        self.users.values().find(|user| user.username == username)
-    }
+struct AppState {
+    db_pool: DbPool
+}
 
-    fn get_user_by_id(&self, id: u64) -> Option<&User> {
-        self.users.get(&id)
-    }
+#[derive(Serialize, ToSchema)]
+struct TokenResponse {
+    token: String,
+}
 
-    fn delete_user_by_id(&mut self, id: &u64) {
-        self.users.remove(&id);
+#[derive(OpenApi)]
+#[openapi(
+    paths(csrf_token, create_task, read_task, read_all_tasks, update_task, delete_task, register_user, login_user),
+    components(schemas(Task, User, TokenResponse)),
+    modifiers(&SecurityAddon)
+)]
+struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+        }
     }
+}
 
-    fn update_user_by_id(&mut self, id: u64, user: User) {
-        self.users.insert(id, user);
-    }   
-
-    //DATABASE SAVING 7:45
-    // Convert haspmap to json
-    // &self, is impl Database (Hashmap)
-    fn save_to_file(&self) -> std::io::Result<()> { 
-        let data = serde_json::to_string(&self)?; // MEANING: convert the struct to a string
-        let mut file = fs::File::create("database.json")?;
-        file.write_all(data.as_bytes())?;
-        Ok(())
-    }
+// Every other route either requires a bearer token or is the CSRF-exempt /login, so a
+// fresh client has no safe GET to seed the double-submit cookie before calling /register.
+// This is that endpoint: it does nothing but let the CSRF middleware set the cookie.
+#[utoipa::path(
+    get,
+    path = "/csrf-token",
+    responses(
+        (status = 204, description = "CSRF cookie set (or refreshed) for this client")
+    )
+)]
+async fn csrf_token() -> HttpResponse {
+    HttpResponse::NoContent().finish()
+}
 
-    fn load_from_file() -> std::io::Result<Self> {
-        match fs::read_to_string("database.json") {
-            Ok(data) if !data.trim().is_empty() => {
-                let database: Database = serde_json::from_str(&data)?;
-                Ok(database)
-            }
-            Ok(_) | Err(_) => {
-                // Return a new database if the file is empty or not found
-                println!("Database file is empty or missing, initializing a new database.");
-                Ok(Database::new())
-            }
-        }
-    }
-    
+#[utoipa::path(
+    post,
+    path = "/task",
+    request_body = Task,
+    responses(
+        (status = 200, description = "Task created"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 500, description = "Failed to persist the task")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn create_task(_auth: AuthenticatedUser, state: web::Data<AppState>, task: web::Json<Task>) -> Result<ApiResponse<Task>, ApiError> {
+    let mut conn = state.db_pool
+    .get()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let task = task.into_inner();
+    insert_task_row(&mut conn, &task).map_err(map_db_error)?;
+    Ok(ApiResponse(task))
 }
 
-struct AppState { 
-    database: Mutex<Database>
+#[allow(dead_code)]
+async fn read_tasks(_auth: AuthenticatedUser, state: web::Data<AppState>, id: web::Path<i64>) -> Result<ApiResponse<Task>, ApiError> {
+    let mut conn = state.db_pool
+    .get()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let task = get_task_row(&mut conn, id.into_inner()).map_err(map_db_error)?;
+    Ok(ApiResponse(task))
 }
 
-async fn create_task(state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
-    let mut database = state.database
-    .lock()
-    .unwrap(); // can replace by expect(msg: "Locked database")
+#[utoipa::path(
+    get,
+    path = "/task/{id}",
+    params(("id" = i64, Path, description = "Task id")),
+    responses(
+        (status = 200, description = "Task found", body = Task),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "Task not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn read_task(_auth: AuthenticatedUser, state: web::Data<AppState>, id: web::Path<i64>) -> Result<ApiResponse<Task>, ApiError> {
+    let mut conn = state.db_pool
+    .get()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let task = get_task_row(&mut conn, id.into_inner()).map_err(map_db_error)?;
+    Ok(ApiResponse(task))
+}
 
-    database.insert(task.into_inner()); // into_inner: get the  extract task and put it in the database
-    let _ = database.save_to_file();
-    HttpResponse::Ok().finish()
+#[utoipa::path(
+    get,
+    path = "/tasks",
+    responses(
+        (status = 200, description = "All tasks", body = [Task]),
+        (status = 401, description = "Missing or invalid bearer token")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn read_all_tasks(_auth: AuthenticatedUser, state: web::Data<AppState>) -> Result<ApiResponse<Vec<Task>>, ApiError> {
+    let mut conn = state.db_pool
+    .get()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let tasks = get_all_task_rows(&mut conn).map_err(map_db_error)?;
+    Ok(ApiResponse(tasks))
 }
 
-async fn read_tasks(state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
-    let mut database = state.database
-    .lock()
-    .unwrap(); // can replace by expect(msg: "Locked database")
+#[utoipa::path(
+    put,
+    path = "/task",
+    request_body = Task,
+    responses(
+        (status = 200, description = "Task updated"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "Task not found"),
+        (status = 500, description = "Failed to persist the task")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn update_task(_auth: AuthenticatedUser, state: web::Data<AppState>, task: web::Json<Task>) -> Result<ApiResponse<Task>, ApiError> {
+    let mut conn = state.db_pool
+    .get()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+    println!("Update database");
 
-    match database.get(id.into_inner()) { // match returns an Option
-        Some(task) => HttpResponse::Ok().json(task),
-        None => HttpResponse::NotFound().finish()
+    let task = task.into_inner();
+    let rows_updated = update_task_row(&mut conn, &task).map_err(map_db_error)?;
+    if rows_updated == 0 {
+        return Err(ApiError::NotFound(format!("Task {} not found", task.id)));
     }
+    println!("Updated database");
+
+    Ok(ApiResponse(task))
 }
 
-async fn read_task(state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
-    let mut database = state.database
-    .lock()
-    .unwrap(); // can replace by expect(msg: "Locked database")
- 
-    match database.get(id.into_inner()) { // match returns an Option
-        Some(task) => HttpResponse::Ok().json(task),
-        None => HttpResponse::NotFound().finish()
+#[utoipa::path(
+    delete,
+    path = "/task/{id}",
+    params(("id" = i64, Path, description = "Task id")),
+    responses(
+        (status = 200, description = "Task deleted"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "Task not found"),
+        (status = 500, description = "Failed to delete the task")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn delete_task(_auth: AuthenticatedUser, state: web::Data<AppState>, id: web::Path<i64>) -> Result<ApiResponse<()>, ApiError> {
+    let mut conn = state.db_pool
+    .get()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let id = id.into_inner();
+    let rows_deleted = delete_task_row(&mut conn, id).map_err(map_db_error)?;
+    if rows_deleted == 0 {
+        return Err(ApiError::NotFound(format!("Task {} not found", id)));
     }
+    Ok(ApiResponse(()))
 }
 
-async fn read_all_tasks(state: web::Data<AppState>) -> impl Responder {
-    let database = state.database
-    .lock()
-    .unwrap(); // can replace by expect(msg: "Locked database")
+#[utoipa::path(
+    post,
+    path = "/register",
+    request_body = User,
+    responses(
+        (status = 200, description = "User registered"),
+        (status = 400, description = "Username or password is blank"),
+        (status = 409, description = "Username already taken"),
+        (status = 500, description = "Failed to hash password or persist the user")
+    )
+)]
+async fn register_user(state: web::Data<AppState>, user: web::Json<User>) -> Result<ApiResponse<()>, ApiError> {
+    let mut conn = state.db_pool
+    .get()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let mut new_user = user.into_inner();
+    if new_user.username.trim().is_empty() || new_user.password.is_empty() {
+        return Err(ApiError::BadRequest("Username and password must not be blank".to_string()));
+    }
+    new_user.password = hash_password(&new_user.password)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    HttpResponse::Ok().json(database.get_all())
+    insert_user(&mut conn, &new_user).map_err(map_db_error)?;
+    Ok(ApiResponse(()))
 }
 
-async fn update_task(state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
-    let mut database = state.database
-    .lock()
-    .unwrap(); // can replace by expect(msg: "Locked database") 
-    println!("Update database");
+#[utoipa::path(
+    post,
+    path = "/login",
+    request_body = User,
+    responses(
+        (status = 200, description = "Login succeeded, returns a bearer token", body = TokenResponse),
+        (status = 401, description = "Invalid username or password")
+    )
+)]
+async fn login_user(state: web::Data<AppState>, user: web::Json<User>) -> Result<ApiResponse<TokenResponse>, ApiError> {
+    let mut conn = state.db_pool
+    .get()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let stored_user = get_user_by_name(&mut conn, &user.username)
+        .map_err(|_| ApiError::Unauthorized("Login failed".to_string()))?;
+
+    if !verify_password(&stored_user.password, &user.password) {
+        return Err(ApiError::Unauthorized("Login failed".to_string()));
+    }
 
-    database.update(task.id, task.clone());
-    println!("Updated database");
+    let token = issue_token(&stored_user).map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(ApiResponse(TokenResponse { token }))
+}
 
-    let _ = database.save_to_file();
-    HttpResponse::Ok().finish() 
-}
-
-async fn delete_task(state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
-    let mut database = state.database
-    .lock()
-    .unwrap(); // can replace by expect(msg: "Locked database")     
-
-    database.delete(&id.into_inner());
-    let _ = database.save_to_file();
-    HttpResponse::Ok().finish()
-} 
-
-async fn register_user(state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {
-    let mut database = state.database
-    .lock()
-    .unwrap(); // can replace by expect(msg: "Locked database")
-    database.insert_user(user.into_inner());
-    let _ = database.save_to_file();
-    HttpResponse::Ok().finish()
-}
-
-async fn login_user(state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {
-    let database = state.database
-    .lock()
-    .unwrap(); // can replace by expect(msg: "Locked database")
-    match database.get_user_by_name(&user.username) {
-        Some(stored_user) if stored_user.password == user.password => {
-            HttpResponse::Ok().body("Login successful")
-        },
-        _ => HttpResponse::Unauthorized().body("Login failed")
-    }
+fn build_pool(database_url: &str) -> DbPool {
+    let manager = ConnectionManager::<SqliteConnection>::new(database_url);
+    r2d2::Pool::builder()
+        .build(manager)
+        .expect("Failed to create database pool")
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let database = match Database::load_from_file() {
-        Ok(database) => database,
-        Err(e) => {
-            println!("Error loading database: {}", e);
-            Database::new()
-        }
-    };
-    // Use AppState to store the locked database (mutex)
+    let database_url = std::env::var("DATABASE_URL").unwrap_or_else(|_| "tasks.db".to_string());
+    let db_pool = build_pool(&database_url);
+    {
+        let mut conn = db_pool.get().expect("Failed to get a database connection");
+        conn.run_pending_migrations(MIGRATIONS)
+            .expect("Failed to run database migrations");
+    }
+
+    // Use AppState to store the pooled connections (replaces the old Mutex<Database>)
     let app_data = web::Data::new(AppState {
-        database: Mutex::new(database) // can shared in multiple threads
+        db_pool // can be shared across threads, each worker checks out its own connection
     });
 
+    const CSRF_EXEMPT_PATHS: &[&str] = &["/login"];
+
     // Create a new HTTP server
     HttpServer::new(move || {
         App::new() // Actix web
@@ -218,12 +415,20 @@ async fn main() -> std::io::Result<()> {
                     .supports_credentials()
                     .max_age(3600)
             )
+            .wrap(actix_web::middleware::from_fn(|req, next| {
+                csrf::csrf_protection(req, next, CSRF_EXEMPT_PATHS)
+            }))
             // cloned? To allow multiple threads, dont worry it not cloning the database, it only clones the web::Data pointer
             .app_data(app_data.clone())
+            .service(
+                SwaggerUi::new("/swagger-ui/{_:.*}")
+                    .url("/api-docs/openapi.json", ApiDoc::openapi())
+            )
+            .route("/csrf-token", web::get().to(csrf_token))
             .route("/task", web::post().to(create_task))
             .route("/tasks", web::get().to(read_all_tasks))
             .route("/task/{id}", web::get().to(read_task))
-            .route("/task", web::put().to(update_task))    
+            .route("/task", web::put().to(update_task))
             .route("/task/{id}", web::delete().to(delete_task))
             .route("/register", web::post().to(register_user))
             .route("/login", web::post().to(login_user))
@@ -231,5 +436,84 @@ async fn main() -> std::io::Result<()> {
     })
     .bind(("127.0.0.1", 8080))?
     .run()
-    .await   
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test};
+
+    // A fresh in-memory SQLite database per test, migrated and capped to a single
+    // connection so every request in a test sees the same (otherwise ephemeral) database.
+    fn test_db_pool() -> DbPool {
+        let manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = r2d2::Pool::builder()
+            .max_size(1)
+            .build(manager)
+            .expect("failed to build in-memory pool");
+        pool.get()
+            .unwrap()
+            .run_pending_migrations(MIGRATIONS)
+            .expect("failed to run migrations");
+        pool
+    }
+
+    #[actix_web::test]
+    async fn missing_bearer_token_is_rejected() {
+        let app_data = web::Data::new(AppState { db_pool: test_db_pool() });
+        let app = test::init_service(
+            App::new()
+                .app_data(app_data.clone())
+                .route("/tasks", web::get().to(read_all_tasks)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/tasks").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    fn bearer_token_for(user: &User) -> String {
+        format!("Bearer {}", issue_token(user).expect("failed to issue token"))
+    }
+
+    #[actix_web::test]
+    async fn update_task_returns_404_when_missing() {
+        let app_data = web::Data::new(AppState { db_pool: test_db_pool() });
+        let app = test::init_service(
+            App::new()
+                .app_data(app_data.clone())
+                .route("/task", web::put().to(update_task)),
+        )
+        .await;
+
+        let user = User { id: 1, username: "alice".to_string(), password: "hash".to_string() };
+        let req = test::TestRequest::put()
+            .uri("/task")
+            .insert_header(("Authorization", bearer_token_for(&user)))
+            .set_json(Task { id: 999, name: "ghost".to_string(), completed: false })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn delete_task_returns_404_when_missing() {
+        let app_data = web::Data::new(AppState { db_pool: test_db_pool() });
+        let app = test::init_service(
+            App::new()
+                .app_data(app_data.clone())
+                .route("/task/{id}", web::delete().to(delete_task)),
+        )
+        .await;
+
+        let user = User { id: 1, username: "alice".to_string(), password: "hash".to_string() };
+        let req = test::TestRequest::delete()
+            .uri("/task/999")
+            .insert_header(("Authorization", bearer_token_for(&user)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,163 @@
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpResponse};
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+
+const COOKIE_NAME: &str = "csrf_token";
+const HEADER_NAME: &str = "X-CSRF-Token";
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn csrf_secret() -> Vec<u8> {
+    std::env::var("CSRF_SECRET")
+        .unwrap_or_else(|_| "dev-csrf-secret-change-me".to_string())
+        .into_bytes()
+}
+
+fn sign(nonce: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(&csrf_secret()).expect("HMAC accepts a key of any size");
+    mac.update(nonce.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn issue_token() -> String {
+    let mut nonce_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce: String = nonce_bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let signature = sign(&nonce);
+    format!("{}.{}", nonce, signature)
+}
+
+fn is_valid_token(token: &str) -> bool {
+    match token.split_once('.') {
+        Some((nonce, signature)) => constant_time_eq(&sign(nonce), signature),
+        None => false,
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+// Double-submit-cookie CSRF protection: a safe GET seeds a signed token cookie, and any
+// mutating request must echo that exact value back in the `X-CSRF-Token` header.
+pub async fn csrf_protection<B>(
+    req: ServiceRequest,
+    next: Next<B>,
+    exempt_paths: &'static [&'static str],
+) -> Result<ServiceResponse<BoxBody>, Error>
+where
+    B: MessageBody + 'static,
+{
+    let method_is_safe = is_safe_method(req.method());
+    let is_exempt = exempt_paths.contains(&req.path());
+
+    if !method_is_safe && !is_exempt {
+        let cookie_token = req.cookie(COOKIE_NAME).map(|cookie| cookie.value().to_string());
+        let header_token = req
+            .headers()
+            .get(HEADER_NAME)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let tokens_match = matches!(
+            (&cookie_token, &header_token),
+            (Some(cookie_value), Some(header_value)) if cookie_value == header_value
+        );
+
+        if !tokens_match || !is_valid_token(&cookie_token.unwrap_or_default()) {
+            let response = HttpResponse::Forbidden().body("Missing or mismatched CSRF token");
+            return Ok(req.into_response(response).map_into_boxed_body());
+        }
+    }
+
+    let needs_token = method_is_safe && req.cookie(COOKIE_NAME).is_none();
+    let res = next.call(req).await?;
+    let mut res = res.map_into_boxed_body();
+
+    if needs_token {
+        let cookie = Cookie::build(COOKIE_NAME, issue_token())
+            .same_site(SameSite::Strict)
+            .path("/")
+            .finish();
+        let _ = res.response_mut().add_cookie(&cookie);
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, web, App};
+
+    async fn ok_handler() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    macro_rules! app_with_csrf {
+        () => {
+            App::new()
+                .wrap(actix_web::middleware::from_fn(|req, next| csrf_protection(req, next, &[])))
+                .route("/", web::get().to(ok_handler))
+                .route("/", web::post().to(ok_handler))
+        };
+    }
+
+    #[actix_web::test]
+    async fn safe_get_seeds_the_csrf_cookie() {
+        let app = test::init_service(app_with_csrf!()).await;
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.response().cookies().any(|c| c.name() == COOKIE_NAME));
+    }
+
+    #[actix_web::test]
+    async fn mutating_request_without_token_is_rejected() {
+        let app = test::init_service(app_with_csrf!()).await;
+
+        let req = test::TestRequest::post().uri("/").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn mutating_request_with_matching_double_submit_token_is_accepted() {
+        let app = test::init_service(app_with_csrf!()).await;
+
+        let get_req = test::TestRequest::get().uri("/").to_request();
+        let get_resp = test::call_service(&app, get_req).await;
+        let token = get_resp
+            .response()
+            .cookies()
+            .find(|c| c.name() == COOKIE_NAME)
+            .expect("GET should seed the CSRF cookie")
+            .value()
+            .to_string();
+
+        let post_req = test::TestRequest::post()
+            .uri("/")
+            .cookie(Cookie::new(COOKIE_NAME, token.clone()))
+            .insert_header((HEADER_NAME, token))
+            .to_request();
+        let post_resp = test::call_service(&app, post_req).await;
+        assert_eq!(post_resp.status(), StatusCode::OK);
+    }
+}
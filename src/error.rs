@@ -0,0 +1,89 @@
+use actix_web::{http::StatusCode, HttpRequest, HttpResponse, Responder, ResponseError};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Serialize;
+use std::fmt;
+
+// Maps domain failures (missing records, duplicate keys, pool or hashing errors, ...) onto a
+// consistent `{ "error": { "code", "message" } }` body instead of bare/panicking responses.
+// This module is shared by both binaries; not every variant is constructed by both.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub enum ApiError {
+    NotFound(String),
+    Unauthorized(String),
+    BadRequest(String),
+    Conflict(String),
+    Internal(String),
+}
+
+impl ApiError {
+    fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound(_) => "NOT_FOUND",
+            ApiError::Unauthorized(_) => "UNAUTHORIZED",
+            ApiError::BadRequest(_) => "BAD_REQUEST",
+            ApiError::Conflict(_) => "CONFLICT",
+            ApiError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            ApiError::NotFound(msg)
+            | ApiError::Unauthorized(msg)
+            | ApiError::BadRequest(msg)
+            | ApiError::Conflict(msg)
+            | ApiError::Internal(msg) => msg,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(serde_json::json!({
+            "error": {
+                "code": self.code(),
+                "message": self.message(),
+            }
+        }))
+    }
+}
+
+// Maps a Diesel query failure onto the closest ApiError variant: a missing row becomes
+// a 404, a unique-constraint violation becomes a 409, anything else is a 500.
+pub fn map_db_error(err: DieselError) -> ApiError {
+    match err {
+        DieselError::NotFound => ApiError::NotFound("Resource not found".to_string()),
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => {
+            ApiError::Conflict(info.message().to_string())
+        }
+        other => ApiError::Internal(other.to_string()),
+    }
+}
+
+// Wraps a successful payload as `{ "data": ... }` so every handler returns the same shape.
+pub struct ApiResponse<T>(pub T);
+
+impl<T: Serialize> Responder for ApiResponse<T> {
+    type Body = actix_web::body::BoxBody;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok().json(serde_json::json!({ "data": self.0 }))
+    }
+}
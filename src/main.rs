@@ -5,127 +5,305 @@ Test endpoints with curl:
 curl http://127.0.0.1:8080/forex
 curl http://127.0.0.1:8080/forex/EURUSD
 
-Update Forex price:
-curl -X PUT -H "Content-Type: application/json" -d '{"symbol": "EURUSD", "price": 1.23}' http://127.0.0.1:8080/forex
+Update Forex price (mutating routes require a CSRF cookie + matching header, see below):
+curl -c cookies.txt http://127.0.0.1:8080/forex
+curl -b cookies.txt -X PUT -H "Content-Type: application/json" \
+  -H "X-CSRF-Token: $(grep csrf_token cookies.txt | cut -f7)" \
+  -d '{"symbol": "EURUSD", "price": 1.23}' http://127.0.0.1:8080/forex
+
+Stream live price changes (optionally filter with ?symbols=EURUSD,USDJPY):
+curl -N http://127.0.0.1:8080/forex/stream
+
+Interactive API docs: http://127.0.0.1:8080/swagger-ui/
  */
 
+mod csrf;
+mod error;
+mod schema;
+
 use actix_cors::Cors;
 use actix_web::{http::header, web, App, HttpServer, HttpResponse, Responder};
-use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use std::collections::HashMap;
+use diesel::prelude::*;
+use diesel::r2d2::{self, ConnectionManager};
+use diesel::sqlite::SqliteConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use error::{map_db_error, ApiError, ApiResponse};
+use futures_util::stream::{self, StreamExt};
 use reqwest::Client;
+use schema::forex_pairs;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+use utoipa::{OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+type DbPool = r2d2::Pool<ConnectionManager<SqliteConnection>>;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Insertable, AsChangeset, ToSchema)]
+#[diesel(table_name = forex_pairs)]
 struct ForexPair {
     symbol: String,
     price: f64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct ForexDatabase {
-    forex_pairs: HashMap<String, ForexPair>,
-}
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_forex_price, get_all_forex_prices, update_forex_price, stream_forex_prices),
+    components(schemas(ForexPair))
+)]
+struct ApiDoc;
 
-impl ForexDatabase {
-    fn new() -> ForexDatabase {
-        ForexDatabase {
-            forex_pairs: HashMap::new(),
-        }
-    }
+// Pairs the background refresher is allowed to write. update_forex_price (PUT /forex)
+// is intentionally not restricted to this list, since an operator may want to track more.
+const TRACKED_SYMBOLS: &[&str] = &["EURUSD", "USDJPY"];
 
-    fn preload(&mut self) {
-        self.forex_pairs.insert(
-            "EURUSD".to_string(),
-            ForexPair {
-                symbol: "EURUSD".to_string(),
-                price: 1.0,
-            },
-        );
-        self.forex_pairs.insert(
-            "USDJPY".to_string(),
-            ForexPair {
-                symbol: "USDJPY".to_string(),
-                price: 1.0,
-            },
-        );
+// Seeds the default pairs on first run; a conflicting symbol is left untouched so a
+// restart never clobbers a price we already fetched. Inserted one row at a time since
+// SQLite can't combine a batch insert with ON CONFLICT in a single statement.
+fn seed_forex_pairs(conn: &mut SqliteConnection) -> QueryResult<usize> {
+    let defaults = vec![
+        ForexPair { symbol: "EURUSD".to_string(), price: 1.0 },
+        ForexPair { symbol: "USDJPY".to_string(), price: 1.0 },
+    ];
+    let mut rows_inserted = 0;
+    for pair in &defaults {
+        rows_inserted += diesel::insert_into(forex_pairs::table)
+            .values(pair)
+            .on_conflict(forex_pairs::symbol)
+            .do_nothing()
+            .execute(conn)?;
     }
+    Ok(rows_inserted)
+}
 
-    fn get(&self, symbol: &str) -> Option<&ForexPair> {
-        self.forex_pairs.get(symbol)
-    }
+fn get_forex_pair(conn: &mut SqliteConnection, symbol: &str) -> QueryResult<ForexPair> {
+    forex_pairs::table.find(symbol).first(conn)
+}
 
-    fn get_all(&self) -> Vec<&ForexPair> {
-        self.forex_pairs.values().collect()
-    }
+fn get_all_forex_pairs(conn: &mut SqliteConnection) -> QueryResult<Vec<ForexPair>> {
+    forex_pairs::table.load(conn)
+}
+
+fn upsert_forex_pair(conn: &mut SqliteConnection, pair: &ForexPair) -> QueryResult<usize> {
+    diesel::insert_into(forex_pairs::table)
+        .values(pair)
+        .on_conflict(forex_pairs::symbol)
+        .do_update()
+        .set(forex_pairs::price.eq(pair.price))
+        .execute(conn)
+}
 
-    fn update(&mut self, symbol: &str, price: f64) {
-        if let Some(pair) = self.forex_pairs.get_mut(symbol) {
-            pair.price = price;
+// Fetches the latest rates over HTTP without touching the database, so callers only
+// need to hold a pooled connection for the short mutation window, never across an await.
+// Derives the EURUSD/USDJPY pairs we actually track from the USD-base response instead
+// of forwarding every raw currency code the API happens to return.
+async fn fetch_latest_rates() -> Result<Vec<(String, f64)>, Box<dyn std::error::Error>> {
+    let client = Client::new();
+    let resp_text = client
+        .get("https://api.exchangerate-api.com/v4/latest/USD")
+        .send()
+        .await?
+        .text()
+        .await?;
+    let resp: serde_json::Value = serde_json::from_str(&resp_text)?;
+    let rates_obj = resp.get("rates").and_then(|v| v.as_object());
+
+    let mut rates = Vec::new();
+    match rates_obj {
+        Some(entries) => {
+            // EURUSD is US dollars per euro, i.e. the inverse of USD->EUR.
+            if let Some(usd_to_eur) = entries.get("EUR").and_then(|v| v.as_f64()).filter(|rate| *rate != 0.0) {
+                rates.push(("EURUSD".to_string(), 1.0 / usd_to_eur));
+            }
+            // USDJPY is Japanese yen per US dollar, i.e. USD->JPY as-is.
+            if let Some(usd_to_jpy) = entries.get("JPY").and_then(|v| v.as_f64()) {
+                rates.push(("USDJPY".to_string(), usd_to_jpy));
+            }
         }
+        None => eprintln!("Rates not found in exchangerate-api.com response"),
     }
 
-    async fn fetch_latest_prices(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let client = Client::new();
-        let urls = vec![
-            "https://api.exchangerate-api.com/v4/latest/USD",
-            "https://api.exchangerate-api.com/v4/latest/EUR",
-        ];
-
-        for url in urls {
-            let resp_text = client.get(url).send().await?.text().await?;
-            let resp: serde_json::Value = serde_json::from_str(&resp_text)?;
-
-            if let Some(rates) = resp.get("rates").and_then(|v| v.as_object()) {
-                for (symbol, price) in rates {
-                    if let Some(price) = price.as_f64() {
-                        self.update(symbol, price);
-                    }
-                }
-            } else {
-                eprintln!("Rates not found in response from {}", url);
-            }
+    Ok(rates)
+}
+
+// Fetches the latest rates, then takes a pooled connection only long enough to upsert
+// them and collect the changed pairs to broadcast to /forex/stream subscribers.
+async fn refresh_forex_prices(state: &web::Data<AppState>) {
+    let rates = match fetch_latest_rates().await {
+        Ok(rates) => rates,
+        Err(e) => {
+            eprintln!("Failed to fetch latest Forex prices: {}", e);
+            return;
         }
+    };
+
+    let updated_pairs = {
+        let mut conn = state.db_pool.get().unwrap(); // can replace by expect(msg: "Pool exhausted")
+        rates
+            .into_iter()
+            .filter(|(symbol, _)| TRACKED_SYMBOLS.contains(&symbol.as_str()))
+            .filter_map(|(symbol, price)| {
+                let pair = ForexPair { symbol, price };
+                upsert_forex_pair(&mut conn, &pair).ok().map(|_| pair)
+            })
+            .collect::<Vec<_>>()
+    };
 
-        Ok(())
+    for pair in updated_pairs {
+        let _ = state.forex_events.send(pair);
     }
 }
 
 struct AppState {
-    forex_data: Mutex<ForexDatabase>,
+    db_pool: DbPool,
+    // Broadcasts every ForexPair mutation so /forex/stream subscribers see live updates.
+    forex_events: broadcast::Sender<ForexPair>,
 }
 
-async fn get_forex_price(state: web::Data<AppState>, symbol: web::Path<String>) -> impl Responder {
-    let forex_data = state.forex_data.lock().unwrap();
-    match forex_data.get(&symbol.into_inner().to_uppercase()) {
-        Some(pair) => HttpResponse::Ok().json(pair),
-        None => HttpResponse::NotFound().finish(),
-    }
+#[utoipa::path(
+    get,
+    path = "/forex/{symbol}",
+    params(("symbol" = String, Path, description = "Currency pair symbol, e.g. EURUSD")),
+    responses(
+        (status = 200, description = "Current price for the pair", body = ForexPair),
+        (status = 404, description = "Unknown symbol")
+    )
+)]
+async fn get_forex_price(state: web::Data<AppState>, symbol: web::Path<String>) -> Result<ApiResponse<ForexPair>, ApiError> {
+    let mut conn = state.db_pool.get().map_err(|e| ApiError::Internal(e.to_string()))?;
+    let pair = get_forex_pair(&mut conn, &symbol.into_inner().to_uppercase()).map_err(map_db_error)?;
+    Ok(ApiResponse(pair))
 }
 
-async fn get_all_forex_prices(state: web::Data<AppState>) -> impl Responder {
-    let forex_data = state.forex_data.lock().unwrap();
-    HttpResponse::Ok().json(forex_data.get_all())
+#[utoipa::path(
+    get,
+    path = "/forex",
+    responses(
+        (status = 200, description = "All known currency pairs", body = [ForexPair])
+    )
+)]
+async fn get_all_forex_prices(state: web::Data<AppState>) -> Result<ApiResponse<Vec<ForexPair>>, ApiError> {
+    let mut conn = state.db_pool.get().map_err(|e| ApiError::Internal(e.to_string()))?;
+    let pairs = get_all_forex_pairs(&mut conn).map_err(map_db_error)?;
+    Ok(ApiResponse(pairs))
+}
+
+#[utoipa::path(
+    put,
+    path = "/forex",
+    request_body = ForexPair,
+    responses(
+        (status = 200, description = "Price updated"),
+        (status = 500, description = "Failed to persist the price")
+    )
+)]
+async fn update_forex_price(state: web::Data<AppState>, pair: web::Json<ForexPair>) -> Result<ApiResponse<ForexPair>, ApiError> {
+    let mut conn = state.db_pool.get().map_err(|e| ApiError::Internal(e.to_string()))?;
+    let pair = pair.into_inner();
+    upsert_forex_pair(&mut conn, &pair).map_err(map_db_error)?;
+    // No subscribers is not an error, just means nobody is listening yet.
+    let _ = state.forex_events.send(pair.clone());
+    Ok(ApiResponse(pair))
+}
+
+#[derive(Deserialize)]
+struct StreamQuery {
+    symbols: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/forex/stream",
+    params(("symbols" = Option<String>, Query, description = "Comma-separated symbols to filter, e.g. EURUSD,USDJPY")),
+    responses(
+        (status = 200, description = "text/event-stream of ForexPair updates as they happen")
+    )
+)]
+async fn stream_forex_prices(state: web::Data<AppState>, query: web::Query<StreamQuery>) -> impl Responder {
+    let wanted_symbols: Option<Vec<String>> = query.symbols.as_ref().map(|symbols| {
+        symbols
+            .split(',')
+            .map(|symbol| symbol.trim().to_uppercase())
+            .collect()
+    });
+
+    let receiver = state.forex_events.subscribe();
+    let events = BroadcastStream::new(receiver).filter_map(move |message| {
+        let wanted_symbols = wanted_symbols.clone();
+        async move {
+            let pair = message.ok()?; // drop the event on a lagged receiver rather than erroring the stream
+            if wanted_symbols
+                .as_ref()
+                .is_some_and(|symbols| !symbols.contains(&pair.symbol))
+            {
+                return None;
+            }
+            let payload = serde_json::to_string(&pair).ok()?;
+            Some(Ok::<_, actix_web::Error>(web::Bytes::from(format!(
+                "data: {}\n\n",
+                payload
+            ))))
+        }
+    });
+
+    // Idle proxies tend to drop SSE connections after ~30-60s of silence.
+    let keep_alive = IntervalStream::new(tokio::time::interval(Duration::from_secs(15)))
+        .map(|_| Ok::<_, actix_web::Error>(web::Bytes::from_static(b": keep-alive\n\n")));
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .streaming(stream::select(events, keep_alive))
 }
 
-async fn update_forex_price(state: web::Data<AppState>, pair: web::Json<ForexPair>) -> impl Responder {
-    let mut forex_data = state.forex_data.lock().unwrap();
-    forex_data.update(&pair.symbol, pair.price);
-    HttpResponse::Ok().finish()
+fn build_pool(database_url: &str) -> DbPool {
+    let manager = ConnectionManager::<SqliteConnection>::new(database_url);
+    r2d2::Pool::builder()
+        .build(manager)
+        .expect("Failed to create database pool")
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let mut forex_data = ForexDatabase::new();
-    forex_data.preload();
-    if let Err(e) = forex_data.fetch_latest_prices().await {
-        eprintln!("Failed to fetch latest Forex prices: {}", e);
+    let database_url = std::env::var("DATABASE_URL").unwrap_or_else(|_| "forex.db".to_string());
+    let db_pool = build_pool(&database_url);
+    {
+        let mut conn = db_pool.get().expect("Failed to get a database connection");
+        conn.run_pending_migrations(MIGRATIONS)
+            .expect("Failed to run database migrations");
+        seed_forex_pairs(&mut conn).expect("Failed to seed default forex pairs");
     }
 
+    let (forex_events, _) = broadcast::channel(100);
     let app_data = web::Data::new(AppState {
-        forex_data: Mutex::new(forex_data),
+        db_pool,
+        forex_events,
     });
 
+    // Populate real prices before we start serving, then keep them fresh in the background.
+    refresh_forex_prices(&app_data).await;
+
+    let refresh_period = std::env::var("FOREX_REFRESH_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(60));
+    {
+        let app_data = app_data.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(refresh_period);
+            ticker.tick().await; // first tick fires immediately; we already refreshed above
+            loop {
+                ticker.tick().await;
+                refresh_forex_prices(&app_data).await;
+            }
+        });
+    }
+
+    const CSRF_EXEMPT_PATHS: &[&str] = &[];
+
     HttpServer::new(move || {
         App::new()
             .wrap(
@@ -139,7 +317,15 @@ async fn main() -> std::io::Result<()> {
                     .supports_credentials()
                     .max_age(3600)
             )
+            .wrap(actix_web::middleware::from_fn(|req, next| {
+                csrf::csrf_protection(req, next, CSRF_EXEMPT_PATHS)
+            }))
             .app_data(app_data.clone())
+            .service(
+                SwaggerUi::new("/swagger-ui/{_:.*}")
+                    .url("/api-docs/openapi.json", ApiDoc::openapi())
+            )
+            .route("/forex/stream", web::get().to(stream_forex_prices))
             .route("/forex/{symbol}", web::get().to(get_forex_price))
             .route("/forex", web::get().to(get_all_forex_prices))
             .route("/forex", web::put().to(update_forex_price))